@@ -2,25 +2,148 @@ use crate::config::Config;
 use crate::manifest::*;
 use crate::runtimes::{RtControllerMsg, RuntimesController};
 use crate::util::{monitor_fs_changes, monitor_heartbeat, time_now};
+use actix::{Actor, AsyncContext, StreamHandler};
 use actix_files::NamedFile;
-use actix_web::{web, HttpResponse, Responder, Result, FromRequest};
-use crossbeam::channel as crossbeam_channel;
-use crossbeam::channel::bounded;
+use actix_web::{web, Error, HttpRequest, HttpResponse, Responder, Result, FromRequest};
+use actix_web_actors::ws;
+use futures::Stream;
 use reqwest;
+use serde::{Deserialize, Serialize};
 use serde_json;
+use rustls_pemfile::{certs, ec_private_keys, pkcs8_private_keys, rsa_private_keys};
+use std::fs::File;
+use std::io::BufReader;
 use std::path::{Path, PathBuf};
+use std::pin::Pin;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::mpsc::channel;
 use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Duration;
+use tokio::sync::broadcast;
+use tokio_stream::wrappers::BroadcastStream;
+use tokio_stream::StreamExt;
 use url::Url;
 use std::io::Write;
 
+/// Named events published on the `/events` SSE channel.
+///
+/// `pub` because `RuntimesController`, `monitor_fs_changes`, and
+/// `monitor_heartbeat` all construct and send these across the
+/// `broadcast::Sender<AppEvent>` handed to them from `start_server`.
+#[derive(Clone, Debug)]
+pub enum AppEvent {
+    AppChanged,
+    RuntimeReady { uri: String },
+    Heartbeat { timestamp: usize },
+}
+
+impl AppEvent {
+    fn name(&self) -> &'static str {
+        match self {
+            AppEvent::AppChanged => "app-changed",
+            AppEvent::RuntimeReady { .. } => "runtime-ready",
+            AppEvent::Heartbeat { .. } => "heartbeat",
+        }
+    }
+
+    fn to_sse_frame(&self) -> String {
+        let data = match self {
+            AppEvent::AppChanged => serde_json::json!({}),
+            AppEvent::RuntimeReady { uri } => serde_json::json!({ "uri": uri }),
+            AppEvent::Heartbeat { timestamp } => serde_json::json!({ "timestamp": timestamp }),
+        };
+        format!("event: {}\ndata: {}\n\n", self.name(), data)
+    }
+}
+
+/// Wraps a `broadcast::Receiver<AppEvent>` as a `Stream` of SSE byte frames,
+/// interleaving a `: keep-alive` comment every 15s so idle connections
+/// survive proxies that drop them.
+struct SseStream {
+    inner: Pin<Box<dyn Stream<Item = Result<actix_web::web::Bytes, actix_web::Error>>>>,
+}
+
+impl SseStream {
+    fn new(rx: broadcast::Receiver<AppEvent>) -> Self {
+        let events = BroadcastStream::new(rx).filter_map(|item| {
+            item.ok()
+                .map(|event| Ok(actix_web::web::Bytes::from(event.to_sse_frame())))
+        });
+        let keep_alive = tokio_stream::wrappers::IntervalStream::new(tokio::time::interval(
+            Duration::from_secs(15),
+        ))
+        .map(|_| Ok(actix_web::web::Bytes::from_static(b": keep-alive\n\n")));
+
+        SseStream {
+            inner: Box::pin(futures::stream::select(events, keep_alive)),
+        }
+    }
+}
+
+impl Stream for SseStream {
+    type Item = Result<actix_web::web::Bytes, actix_web::Error>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.inner.as_mut().poll_next(cx)
+    }
+}
+
 struct AppState {
     app_dir: String,
     designer_string: String,
-    tx_handler: std::sync::mpsc::Sender<RtControllerMsg>, 
-    rx_uri_handler: crossbeam_channel::Receiver<Url>,
+    tx_handler: std::sync::mpsc::Sender<RtControllerMsg>,
     last_heartbeat: web::Data<AtomicUsize>,
+    event_tx: broadcast::Sender<AppEvent>,
+    http_client: reqwest::Client,
+    /// Used only by the `/ws` streaming path. `http_client`'s `request_timeout`
+    /// is a *total* deadline that would cut off a long-running streamed eval
+    /// mid-response; this client instead bounds idle time between reads.
+    streaming_http_client: reqwest::Client,
+    request_timeout: Duration,
+}
+
+/// Errors surfaced by the runtime proxy (`eval`, `pipeline_post`), mapped to
+/// the HTTP status code a caller should act on rather than panicking the
+/// worker thread.
+#[derive(Debug)]
+enum ProxyError {
+    /// The runtime URI couldn't be obtained, or the downstream request failed.
+    BadGateway(String),
+    /// The configured per-request timeout elapsed.
+    GatewayTimeout(String),
+    /// The submitted manifest couldn't be parsed.
+    BadRequest(String),
+    /// A local IO error (e.g. writing `app.json`).
+    Internal(String),
+}
+
+impl std::fmt::Display for ProxyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ProxyError::BadGateway(msg) => write!(f, "bad gateway: {msg}"),
+            ProxyError::GatewayTimeout(msg) => write!(f, "gateway timeout: {msg}"),
+            ProxyError::BadRequest(msg) => write!(f, "bad request: {msg}"),
+            ProxyError::Internal(msg) => write!(f, "internal error: {msg}"),
+        }
+    }
+}
+
+impl actix_web::ResponseError for ProxyError {
+    fn status_code(&self) -> actix_web::http::StatusCode {
+        use actix_web::http::StatusCode;
+        match self {
+            ProxyError::BadGateway(_) => StatusCode::BAD_GATEWAY,
+            ProxyError::GatewayTimeout(_) => StatusCode::GATEWAY_TIMEOUT,
+            ProxyError::BadRequest(_) => StatusCode::BAD_REQUEST,
+            ProxyError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        HttpResponse::build(self.status_code())
+            .json(serde_json::json!({ "error": self.to_string() }))
+    }
 }
 
 async fn run(data: web::Data<AppState>) -> impl Responder {
@@ -47,33 +170,448 @@ async fn ping(data: web::Data<AppState>) -> impl Responder {
     HttpResponse::Ok().body(timestamp.to_string())
 }
 
-async fn eval(
-    data: web::Data<AppState>,
-    req: web::Json<Manifests>,
-) -> impl Responder {
-    let rt = req.manifests[0].runtime.clone();
-    let tx_handler = &data.tx_handler;
-    tx_handler.send(RtControllerMsg::GetUri(rt)).unwrap();
-    let rx_uri_handler = &data.rx_uri_handler;
-    let uri = rx_uri_handler.recv().unwrap().join("eval").unwrap();
-    let manifest = req.manifests[0].calls.clone();
+/// Payload posted to a runtime's `/eval`: the node's own calls plus the
+/// resolved `RuntimeResponse` of every manifest it depends on, in the order
+/// `depends_on` lists them.
+#[derive(Serialize)]
+struct PipelineCall<'a, C: Serialize> {
+    calls: &'a C,
+    upstream: Vec<&'a RuntimeResponse>,
+}
 
-    let client = reqwest::Client::new();
+/// Why `topological_levels` couldn't order the manifest set, distinct from
+/// a plain "here are the levels" success so callers can report the right
+/// 400 message instead of a misleading "cycle detected".
+enum TopoError {
+    /// The same manifest id appears more than once in the payload.
+    DuplicateId(String),
+    /// A `depends_on` entry doesn't name any manifest in the payload.
+    DanglingDependency { node: String, depends_on: String },
+    /// The ids still blocked on each other once no more progress can be made.
+    Cycle(Vec<String>),
+}
+
+/// Groups manifest ids into topologically-sorted levels via Kahn's
+/// algorithm, so nodes within a level have no dependency on one another and
+/// can be dispatched concurrently.
+fn topological_levels(manifests: &[Manifest]) -> std::result::Result<Vec<Vec<String>>, TopoError> {
+    use std::collections::{HashMap, HashSet};
+
+    let mut ids: HashSet<&str> = HashSet::new();
+    for manifest in manifests {
+        if !ids.insert(manifest.id.as_str()) {
+            return Err(TopoError::DuplicateId(manifest.id.clone()));
+        }
+    }
+
+    for manifest in manifests {
+        for dep in &manifest.depends_on {
+            if !ids.contains(dep.as_str()) {
+                return Err(TopoError::DanglingDependency {
+                    node: manifest.id.clone(),
+                    depends_on: dep.clone(),
+                });
+            }
+        }
+    }
+
+    let mut indegree: HashMap<&str, usize> = HashMap::new();
+    let mut dependents: HashMap<&str, Vec<&str>> = HashMap::new();
+
+    for manifest in manifests {
+        indegree.entry(manifest.id.as_str()).or_insert(0);
+        for dep in &manifest.depends_on {
+            *indegree.entry(manifest.id.as_str()).or_insert(0) += 1;
+            dependents.entry(dep.as_str()).or_insert_with(Vec::new).push(manifest.id.as_str());
+        }
+    }
+
+    let mut remaining: HashSet<&str> = ids;
+    let mut levels = Vec::new();
+
+    while !remaining.is_empty() {
+        let ready: Vec<&str> = remaining
+            .iter()
+            .copied()
+            .filter(|id| indegree.get(id).copied().unwrap_or(0) == 0)
+            .collect();
+
+        if ready.is_empty() {
+            let mut stuck: Vec<String> = remaining.into_iter().map(String::from).collect();
+            stuck.sort();
+            return Err(TopoError::Cycle(stuck));
+        }
+
+        for id in &ready {
+            remaining.remove(id);
+            if let Some(deps) = dependents.get(id) {
+                for dependent in deps {
+                    if let Some(count) = indegree.get_mut(dependent) {
+                        *count -= 1;
+                    }
+                }
+            }
+        }
+
+        levels.push(ready.into_iter().map(String::from).collect());
+    }
+
+    Ok(levels)
+}
 
+/// Resolves the runtime URI for a single manifest node, bounded by the
+/// configured per-request timeout.
+///
+/// Each call gets its own `oneshot` reply channel rather than sharing one
+/// rendezvous receiver across the app, so concurrent callers (multiple
+/// pipeline nodes in a level, multiple `/ws` evals) can't steal the uri
+/// meant for another request. Awaiting the reply (instead of a blocking
+/// `recv`) also keeps this safe to call from an async task on a
+/// single-threaded actix worker.
+async fn resolve_node_uri(data: &web::Data<AppState>, manifest: &Manifest) -> std::result::Result<Url, ProxyError> {
+    let (reply_tx, reply_rx) = tokio::sync::oneshot::channel();
+
+    data.tx_handler
+        .send(RtControllerMsg::GetUri(manifest.runtime.clone(), reply_tx))
+        .map_err(|err| ProxyError::BadGateway(err.to_string()))?;
+
+    let uri = tokio::time::timeout(data.request_timeout, reply_rx)
+        .await
+        .map_err(|_| {
+            ProxyError::GatewayTimeout(format!("timed out waiting for runtime uri for node {}", manifest.id))
+        })?
+        .map_err(|_| {
+            ProxyError::BadGateway(format!(
+                "runtime controller dropped without a uri for node {}",
+                manifest.id
+            ))
+        })?;
+
+    uri.join("eval").map_err(|err| ProxyError::Internal(err.to_string()))
+}
 
-    let res = client
-        .post(uri)
-        .json(&manifest)
+/// Dispatches one manifest node to its already-resolved runtime URI,
+/// passing in the `RuntimeResponse`s of its upstream dependencies.
+///
+/// A node with no `depends_on` posts the same raw `calls` body the runtime
+/// always expected, so plain single-manifest evals are wire-compatible with
+/// existing runtimes; only nodes that actually have upstream results get
+/// wrapped in the `{"calls": ..., "upstream": [...]}` envelope.
+async fn dispatch_node<'a>(
+    data: &web::Data<AppState>,
+    uri: Url,
+    manifest: &'a Manifest,
+    upstream: Vec<&'a RuntimeResponse>,
+) -> std::result::Result<RuntimeResponse, ProxyError> {
+    let request = data.http_client.post(uri);
+    let request = if upstream.is_empty() {
+        request.json(&manifest.calls)
+    } else {
+        request.json(&PipelineCall { calls: &manifest.calls, upstream })
+    };
+
+    request
         .send()
         .await
-        .unwrap()
+        .map_err(|err| {
+            if err.is_timeout() {
+                ProxyError::GatewayTimeout(err.to_string())
+            } else {
+                ProxyError::BadGateway(err.to_string())
+            }
+        })?
         .json::<RuntimeResponse>()
         .await
-        .unwrap();
+        .map_err(|err| ProxyError::BadGateway(err.to_string()))
+}
 
-    let response = serde_json::to_string(&res).unwrap();
+/// Parses a `/eval` or `/ws` request body into `Manifests`, defaulting the
+/// `id`/`depends_on` fields the pipeline executor relies on. Those fields
+/// postdate the original single-manifest wire format, so legacy callers'
+/// payloads omit them entirely; rather than require every such payload to be
+/// updated (or `manifest.rs`'s `Manifest` to special-case an absent id), fill
+/// in a synthetic per-index id and an empty dependency list before handing
+/// the JSON to `Manifest`'s own `Deserialize` impl.
+fn parse_manifests_body(body: &[u8]) -> std::result::Result<Manifests, ProxyError> {
+    let mut value: serde_json::Value =
+        serde_json::from_slice(body).map_err(|err| ProxyError::BadRequest(err.to_string()))?;
+
+    if let Some(manifests) = value.get_mut("manifests").and_then(|m| m.as_array_mut()) {
+        for (index, manifest) in manifests.iter_mut().enumerate() {
+            if let Some(obj) = manifest.as_object_mut() {
+                obj.entry("id")
+                    .or_insert_with(|| serde_json::Value::String(format!("node-{index}")));
+                obj.entry("depends_on")
+                    .or_insert_with(|| serde_json::Value::Array(Vec::new()));
+            }
+        }
+    }
+
+    serde_json::from_value(value).map_err(|err| ProxyError::BadRequest(err.to_string()))
+}
 
-    HttpResponse::Ok().body(response)
+/// Runs every manifest in a `Manifests` payload as a pipeline: nodes are
+/// topologically ordered on their `depends_on` ids, independent nodes at the
+/// same level run concurrently, and each node's resolved `RuntimeResponse`
+/// is threaded into the nodes that depend on it.
+async fn eval(
+    data: web::Data<AppState>,
+    body: web::Bytes,
+) -> std::result::Result<HttpResponse, ProxyError> {
+    let req: Manifests = parse_manifests_body(&body)?;
+    let manifests = &req.manifests;
+    if manifests.is_empty() {
+        return Err(ProxyError::BadRequest(String::from("manifests array is empty")));
+    }
+
+    let levels = topological_levels(manifests).map_err(|err| match err {
+        TopoError::DuplicateId(id) => {
+            ProxyError::BadRequest(format!("duplicate manifest id: {id}"))
+        }
+        TopoError::DanglingDependency { node, depends_on } => ProxyError::BadRequest(format!(
+            "manifest {node} depends on unknown id {depends_on}"
+        )),
+        TopoError::Cycle(stuck) => {
+            ProxyError::BadRequest(format!("cycle detected among manifest ids: {}", stuck.join(", ")))
+        }
+    })?;
+
+    let mut results: std::collections::HashMap<String, RuntimeResponse> = std::collections::HashMap::new();
+
+    for level in levels {
+        // Runtime URIs are resolved one node at a time, awaiting each before
+        // the next; the actual runtime calls then run concurrently.
+        let mut dispatches = Vec::with_capacity(level.len());
+        for node_id in &level {
+            let manifest = manifests
+                .iter()
+                .find(|m| &m.id == node_id)
+                .expect("node id came from this manifest set");
+            let uri = resolve_node_uri(&data, manifest).await?;
+            let upstream = manifest
+                .depends_on
+                .iter()
+                .filter_map(|dep| results.get(dep))
+                .collect();
+            dispatches.push(dispatch_node(&data, uri, manifest, upstream));
+        }
+
+        let level_results = futures::future::join_all(dispatches).await;
+        for (node_id, result) in level.into_iter().zip(level_results) {
+            results.insert(node_id, result?);
+        }
+    }
+
+    // Pre-pipeline callers posted a single manifest and expected a bare
+    // RuntimeResponse body back. Keep that wire format for the single-node,
+    // dependency-free case so those callers don't see a shape change; real
+    // multi-node pipelines get the `{node_id: RuntimeResponse}` map.
+    let response = if let [only] = manifests {
+        let result = results
+            .remove(&only.id)
+            .expect("the only node's id was just inserted into results above");
+        serde_json::to_string(&result)
+    } else {
+        serde_json::to_string(&results)
+    }
+    .map_err(|err| ProxyError::Internal(err.to_string()))?;
+
+    Ok(HttpResponse::Ok().body(response))
+}
+
+/// Inbound payload for a `/ws` eval request: a `Manifests` submission tagged
+/// with a client-supplied id so concurrent evals don't interleave.
+#[derive(Deserialize)]
+struct WsEvalRequest {
+    id: String,
+    #[serde(flatten)]
+    payload: Manifests,
+}
+
+/// Outbound frame on the `/ws` eval channel: a chunk of the runtime's
+/// streamed response, an error, or the terminal `done` marker, each tagged
+/// with `id`.
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum WsEvalFrame<'a> {
+    Chunk { id: &'a str, data: &'a str },
+    Done { id: &'a str },
+    Error { id: &'a str, message: String },
+}
+
+/// Actor backing one `/ws` connection; multiplexes concurrent eval requests
+/// over the single socket, each tagged by the client-supplied request id.
+struct EvalSocket {
+    state: web::Data<AppState>,
+}
+
+impl Actor for EvalSocket {
+    type Context = ws::WebsocketContext<Self>;
+}
+
+impl StreamHandler<Result<ws::Message, ws::ProtocolError>> for EvalSocket {
+    fn handle(&mut self, msg: Result<ws::Message, ws::ProtocolError>, ctx: &mut Self::Context) {
+        let msg = match msg {
+            Ok(msg) => msg,
+            Err(_) => return,
+        };
+        match msg {
+            ws::Message::Ping(bytes) => ctx.pong(&bytes),
+            ws::Message::Text(text) => {
+                let req: WsEvalRequest = match serde_json::from_str(&text) {
+                    Ok(req) => req,
+                    Err(err) => {
+                        ctx.text(
+                            serde_json::to_string(&WsEvalFrame::Error {
+                                id: "",
+                                message: err.to_string(),
+                            })
+                            .unwrap(),
+                        );
+                        return;
+                    }
+                };
+                let addr = ctx.address();
+                let state = self.state.clone();
+                actix::spawn(async move {
+                    stream_eval(state, req, addr).await;
+                });
+            }
+            ws::Message::Close(reason) => ctx.close(reason),
+            _ => {}
+        }
+    }
+}
+
+/// Resolves the runtime URI for a tagged eval request, streams the runtime's
+/// chunked response back to the socket, and finishes with a `done` frame.
+/// If any step fails, an `error` frame is sent instead and no `done` follows.
+async fn stream_eval(
+    state: web::Data<AppState>,
+    req: WsEvalRequest,
+    addr: actix::Addr<EvalSocket>,
+) {
+    let id = req.id;
+    let Some(manifest_entry) = req.payload.manifests.get(0) else {
+        send_ws_frame(&addr, WsEvalFrame::Error { id: &id, message: String::from("manifests array is empty") });
+        return;
+    };
+    let rt = manifest_entry.runtime.clone();
+
+    // Each call gets its own oneshot reply so a concurrent eval on another
+    // connection can't receive the uri meant for this one, and awaiting it
+    // (rather than a blocking recv) doesn't stall the actix worker.
+    let (reply_tx, reply_rx) = tokio::sync::oneshot::channel();
+    if let Err(err) = state.tx_handler.send(RtControllerMsg::GetUri(rt, reply_tx)) {
+        send_ws_frame(&addr, WsEvalFrame::Error { id: &id, message: err.to_string() });
+        return;
+    }
+    let uri = match tokio::time::timeout(state.request_timeout, reply_rx).await {
+        Ok(Ok(uri)) => match uri.join("eval") {
+            Ok(uri) => uri,
+            Err(err) => {
+                send_ws_frame(&addr, WsEvalFrame::Error { id: &id, message: err.to_string() });
+                return;
+            }
+        },
+        Ok(Err(_)) => {
+            send_ws_frame(
+                &addr,
+                WsEvalFrame::Error {
+                    id: &id,
+                    message: String::from("runtime controller dropped without a uri"),
+                },
+            );
+            return;
+        }
+        Err(_) => {
+            send_ws_frame(
+                &addr,
+                WsEvalFrame::Error { id: &id, message: String::from("timed out waiting for runtime uri") },
+            );
+            return;
+        }
+    };
+    let manifest = manifest_entry.calls.clone();
+
+    let response = match state.streaming_http_client.post(uri).json(&manifest).send().await {
+        Ok(response) => response,
+        Err(err) => {
+            send_ws_frame(&addr, WsEvalFrame::Error { id: &id, message: err.to_string() });
+            return;
+        }
+    };
+
+    // A multibyte UTF-8 character can land on a chunk boundary, so decoding
+    // each chunk independently (e.g. via from_utf8_lossy) can turn a split
+    // character into a `�` in the middle of legitimate output. Buffer bytes
+    // across chunks and only emit the prefix that's valid UTF-8 so far,
+    // holding the rest back until the rest of the character arrives.
+    let mut body = response.bytes_stream();
+    let mut pending = Vec::new();
+    while let Some(chunk) = body.next().await {
+        match chunk {
+            Ok(bytes) => {
+                pending.extend_from_slice(&bytes);
+                let valid_len = match std::str::from_utf8(&pending) {
+                    Ok(_) => pending.len(),
+                    Err(err) => err.valid_up_to(),
+                };
+                if valid_len > 0 {
+                    let data = String::from_utf8(pending.drain(..valid_len).collect())
+                        .expect("valid_len was validated by str::from_utf8 above");
+                    send_ws_frame(&addr, WsEvalFrame::Chunk { id: &id, data: &data });
+                }
+            }
+            Err(err) => {
+                send_ws_frame(&addr, WsEvalFrame::Error { id: &id, message: err.to_string() });
+                return;
+            }
+        }
+    }
+
+    if !pending.is_empty() {
+        // The stream ended mid-character; nothing left to wait for, so flush
+        // what's left rather than silently dropping trailing bytes.
+        let data = String::from_utf8_lossy(&pending).to_string();
+        send_ws_frame(&addr, WsEvalFrame::Chunk { id: &id, data: &data });
+    }
+
+    send_ws_frame(&addr, WsEvalFrame::Done { id: &id });
+}
+
+fn send_ws_frame(addr: &actix::Addr<EvalSocket>, frame: WsEvalFrame) {
+    addr.do_send(SendText(serde_json::to_string(&frame).unwrap()));
+}
+
+struct SendText(String);
+
+impl actix::Message for SendText {
+    type Result = ();
+}
+
+impl actix::Handler<SendText> for EvalSocket {
+    type Result = ();
+
+    fn handle(&mut self, msg: SendText, ctx: &mut Self::Context) {
+        ctx.text(msg.0);
+    }
+}
+
+async fn ws_eval(
+    req: HttpRequest,
+    stream: web::Payload,
+    data: web::Data<AppState>,
+) -> Result<HttpResponse, Error> {
+    ws::start(EvalSocket { state: data }, &req, stream)
+}
+
+async fn events(data: web::Data<AppState>) -> impl Responder {
+    let rx = data.event_tx.subscribe();
+    HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .append_header(("Cache-Control", "no-cache"))
+        .streaming(SseStream::new(rx))
 }
 
 async fn pipeline(data: web::Data<AppState>) -> Result<NamedFile> {
@@ -83,18 +621,129 @@ async fn pipeline(data: web::Data<AppState>) -> Result<NamedFile> {
     Ok(NamedFile::open(design_path)?)
 }
 
-async fn pipeline_post(data: web::Data<AppState>, req: String) -> impl Responder {
+async fn pipeline_post(
+    data: web::Data<AppState>,
+    req: String,
+) -> std::result::Result<HttpResponse, ProxyError> {
     let design_path = Path::new(&data.app_dir).join("app.json");
-    let design_path_str = design_path.to_str().unwrap();
-    println!("design path is {design_path_str}");
-    let mut output = std::fs::File::create(design_path).unwrap();
-    write!(output, "{}", req).ok();
-    HttpResponse::Ok().body("{}")
+    println!("design path is {}", design_path.display());
+    let mut output = File::create(&design_path).map_err(|err| ProxyError::Internal(err.to_string()))?;
+    write!(output, "{}", req).map_err(|err| ProxyError::Internal(err.to_string()))?;
+    Ok(HttpResponse::Ok().body("{}"))
+}
+
+/// Where the HTTP server should listen, resolved from `[server]` in
+/// `hal9.toml` with the CLI `port` argument acting as an override rather
+/// than the only option.
+enum BindAddress {
+    Unix { path: PathBuf, reuse: bool },
+    Tcp { host: String, port: u16 },
+}
+
+const DEFAULT_PORT: u16 = 8080;
+
+fn resolve_bind_address(conf: &Config, port_override: Option<u16>) -> BindAddress {
+    let Some(server) = conf.server.as_ref() else {
+        return BindAddress::Tcp {
+            host: String::from("127.0.0.1"),
+            port: port_override.unwrap_or(DEFAULT_PORT),
+        };
+    };
+
+    if let Some(socket_path) = server.address.strip_prefix("unix:") {
+        return BindAddress::Unix {
+            path: PathBuf::from(socket_path),
+            reuse: server.reuse,
+        };
+    }
+
+    match server.address.rsplit_once(':') {
+        Some((host, port_str)) => {
+            let port = port_override
+                .or_else(|| port_str.parse().ok())
+                .unwrap_or(DEFAULT_PORT);
+            BindAddress::Tcp { host: host.to_string(), port }
+        }
+        None => BindAddress::Tcp {
+            host: server.address.clone(),
+            port: port_override.unwrap_or(DEFAULT_PORT),
+        },
+    }
+}
+
+/// Loads the `[tls]` cert/key pair from `hal9.toml` into a `rustls::ServerConfig`,
+/// or `None` when no TLS config is present. Fails fast if only one of
+/// `cert`/`key` is set, or if either file doesn't parse.
+fn build_tls_config(conf: &Config) -> std::io::Result<Option<rustls::ServerConfig>> {
+    let Some(tls) = conf.tls.as_ref() else {
+        return Ok(None);
+    };
+
+    let (cert_path, key_path) = match (&tls.cert, &tls.key) {
+        (Some(cert), Some(key)) => (cert, key),
+        (None, None) => return Ok(None),
+        _ => {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "[tls] requires both cert and key to be set",
+            ))
+        }
+    };
+
+    let cert_chain = certs(&mut BufReader::new(File::open(cert_path)?))
+        .map_err(|_| {
+            std::io::Error::new(std::io::ErrorKind::InvalidData, "failed to parse TLS certificate")
+        })?
+        .into_iter()
+        .map(rustls::Certificate)
+        .collect();
+
+    let key = load_private_key(key_path)?;
+
+    let server_config = rustls::ServerConfig::builder()
+        .with_safe_defaults()
+        .with_no_client_auth()
+        .with_single_cert(cert_chain, key)
+        .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err.to_string()))?;
+
+    Ok(Some(server_config))
+}
+
+/// Tries each private-key PEM format `rustls_pemfile` supports in turn.
+/// `tls.key` is most commonly PKCS#8, but RSA keys (`openssl genrsa`) emit
+/// PKCS#1 and EC keys commonly emit SEC1 — rejecting those with a
+/// PKCS#8-specific error is a common source of confusing TLS setup failures.
+fn load_private_key(key_path: &Path) -> std::io::Result<rustls::PrivateKey> {
+    let pkcs8 = pkcs8_private_keys(&mut BufReader::new(File::open(key_path)?)).map_err(|_| {
+        std::io::Error::new(std::io::ErrorKind::InvalidData, "failed to parse TLS private key")
+    })?;
+    if let Some(key) = pkcs8.into_iter().next() {
+        return Ok(rustls::PrivateKey(key));
+    }
+
+    let rsa = rsa_private_keys(&mut BufReader::new(File::open(key_path)?)).map_err(|_| {
+        std::io::Error::new(std::io::ErrorKind::InvalidData, "failed to parse TLS private key")
+    })?;
+    if let Some(key) = rsa.into_iter().next() {
+        return Ok(rustls::PrivateKey(key));
+    }
+
+    let ec = ec_private_keys(&mut BufReader::new(File::open(key_path)?)).map_err(|_| {
+        std::io::Error::new(std::io::ErrorKind::InvalidData, "failed to parse TLS private key")
+    })?;
+    if let Some(key) = ec.into_iter().next() {
+        return Ok(rustls::PrivateKey(key));
+    }
+
+    Err(std::io::Error::new(
+        std::io::ErrorKind::InvalidData,
+        "no private keys found in TLS key file (expected PKCS#8, PKCS#1 RSA, or SEC1 EC PEM)",
+    ))
 }
 
 // #[actix_web::main]
 #[tokio::main]
-pub async fn start_server(app_path: String, port: u16) -> std::io::Result<()> {
+pub async fn start_server(app_path: String, port: Option<u16>) -> std::io::Result<()> {
     use actix_web::{web, App, HttpServer};
 
     let app_path_to_monitor = app_path.clone();
@@ -103,21 +752,29 @@ pub async fn start_server(app_path: String, port: u16) -> std::io::Result<()> {
 
     let config_path = PathBuf::new().join(app_path).join("hal9.toml");
     let conf = Config::parse(config_path);
+    let bind_address = resolve_bind_address(&conf, port);
 
     let (tx, rx) = channel();
-    let (tx_uri, rx_uri) = bounded(0);
-    let rx_uri_handler = rx_uri.clone();
 
-    let runtimes_controller = RuntimesController::new(conf.runtimes.clone(), app_path_for_controller, rx, tx_uri);
+    let (event_tx, _) = broadcast::channel::<AppEvent>(64);
+
+    let runtimes_controller = RuntimesController::new(
+        conf.runtimes.clone(),
+        app_path_for_controller,
+        rx,
+        event_tx.clone(),
+    );
 
     runtimes_controller.monitor().unwrap();
 
     tx.send(RtControllerMsg::StartAll).unwrap();
-    tx.send(RtControllerMsg::GetUri(String::from("r"))).unwrap();
+    let (warmup_tx, _warmup_rx) = tokio::sync::oneshot::channel();
+    tx.send(RtControllerMsg::GetUri(String::from("r"), warmup_tx)).unwrap();
 
     let tx_fs = tx.clone();
+    let event_tx_fs = event_tx.clone();
 
-    monitor_fs_changes(app_path_to_monitor, 1000, tx_fs).await;
+    monitor_fs_changes(app_path_to_monitor, 1000, tx_fs, event_tx_fs).await;
 
     let last_heartbeat = web::Data::new(AtomicUsize::new(time_now().try_into().unwrap()));
     let last_heartbeat_arc= Arc::clone(&last_heartbeat);
@@ -126,7 +783,18 @@ pub async fn start_server(app_path: String, port: u16) -> std::io::Result<()> {
     let designer_bytes = include_bytes!("../resources/client.html");
     let designer_string: String = String::from_utf8_lossy(designer_bytes).to_string();
 
+    let request_timeout = Duration::from_millis(conf.request_timeout_ms.unwrap_or(30_000));
+    let http_client = reqwest::Client::builder()
+        .timeout(request_timeout)
+        .build()
+        .expect("failed to build reqwest client");
+    let streaming_http_client = reqwest::Client::builder()
+        .read_timeout(request_timeout)
+        .build()
+        .expect("failed to build streaming reqwest client");
+
     let tx_handler = tx.clone();
+    let event_tx_app = event_tx.clone();
     let http_server = HttpServer::new(move || {
         App::new()
             .app_data(web::Data::new(
@@ -134,8 +802,11 @@ pub async fn start_server(app_path: String, port: u16) -> std::io::Result<()> {
                     app_dir: app_path_data.clone(),
                     designer_string: designer_string.clone(),
                     tx_handler: tx_handler.clone(),
-                    rx_uri_handler: rx_uri_handler.clone(),
-                    last_heartbeat: last_heartbeat.clone()
+                    last_heartbeat: last_heartbeat.clone(),
+                    event_tx: event_tx_app.clone(),
+                    http_client: http_client.clone(),
+                    streaming_http_client: streaming_http_client.clone(),
+                    request_timeout,
                 }
             ))
             .route("/pipeline", web::get().to(pipeline))
@@ -144,20 +815,50 @@ pub async fn start_server(app_path: String, port: u16) -> std::io::Result<()> {
             .route("/", web::get().to(run))
             .service(web::resource("/ping").to(ping))
             .service(web::resource("/eval").route(web::post().to(eval)))
+            .service(web::resource("/ws").route(web::get().to(ws_eval)))
+            .service(web::resource("/events").route(web::get().to(events)))
     })
-    .disable_signals()
-    .bind(("127.0.0.1", port))
-    .unwrap();
+    .disable_signals();
+
+    let (http_server, unix_socket_path) = match &bind_address {
+        BindAddress::Unix { path, reuse } => {
+            if conf.tls.as_ref().is_some_and(|tls| tls.cert.is_some() || tls.key.is_some()) {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    "[tls] is configured but [server] address is a unix socket; TLS only applies to TCP listeners, so this would silently serve plaintext",
+                ));
+            }
+            if *reuse && path.exists() {
+                std::fs::remove_file(path)?;
+            }
+            let http_server = http_server.bind_uds(path)?;
+            println!("server listening on unix socket {}", path.display());
+            (http_server, Some(path.clone()))
+        }
+        BindAddress::Tcp { host, port } => {
+            let http_server = match build_tls_config(&conf)? {
+                Some(tls_config) => http_server.bind_rustls((host.as_str(), *port), tls_config)?,
+                None => http_server.bind((host.as_str(), *port))?,
+            };
+            let myport = http_server.addrs().pop().unwrap().port();
+            println!("server listening on port {myport}");
+            (http_server, None)
+        }
+    };
 
-    let myport = http_server.addrs().pop().unwrap().port();
     let http_server = http_server.run();
 
-    println!("server listening on port {myport}");
-
     let http_server_handle = http_server.handle();
 
     let tx_heartbeat = tx.clone();
-    monitor_heartbeat(http_server_handle, last_heartbeat_arc, 60, tx_heartbeat);
+    let event_tx_heartbeat = event_tx.clone();
+    monitor_heartbeat(http_server_handle, last_heartbeat_arc, 60, tx_heartbeat, event_tx_heartbeat);
+
+    let result = tokio::spawn(http_server).await?;
+
+    if let Some(path) = unix_socket_path {
+        std::fs::remove_file(path).ok();
+    }
 
-    tokio::spawn(http_server).await?
+    result
 }